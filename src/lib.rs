@@ -1,51 +1,256 @@
 #![feature(const_generics)]
 #![allow(incomplete_features)]
 
-use std::num::NonZeroUsize;
+use std::convert::TryFrom;
+use std::iter::FusedIterator;
+use std::num::{NonZeroU16, NonZeroU32, NonZeroU64, NonZeroUsize};
+
+/// A generation counter backing store: an unsigned, non-zero integer type that a
+/// `GenerationalArena` can stamp slots with.
+///
+/// Parameterizing `GenerationIndex`/`Slot`/`GenerationCounter` over this trait lets callers trade
+/// maximum generation count (and thus wrap-around safety margin) for a smaller per-slot memory
+/// footprint, e.g. `NonZeroU16` instead of the default `NonZeroUsize`. The narrower the type, the
+/// sooner `next` returns `None` and the counter is considered exhausted.
+pub trait GenNum: Copy + Eq + Ord + std::fmt::Debug {
+    /// The first generation value a freshly initialized arena hands out.
+    fn new() -> Self;
+
+    /// Advances to the next generation, or `None` if the counter is exhausted.
+    fn next(self) -> Option<Self>;
+
+    /// The underlying numeric value, for bit-packing and comparisons.
+    fn get(self) -> usize;
+
+    /// Reconstructs a generation value from its numeric representation (the inverse of `get`),
+    /// or `None` if `value` is zero or out of range for this type. Used by
+    /// `GenerationIndex::from_bits` to rebuild a generation directly, without replaying `next`
+    /// from scratch.
+    fn from_usize(value: usize) -> Option<Self>;
+}
+
+macro_rules! impl_gen_num {
+    ($nz:ty, $prim:ty) => {
+        impl GenNum for $nz {
+            #[inline]
+            fn new() -> Self {
+                <$nz>::new(1).unwrap()
+            }
+
+            #[inline]
+            fn next(self) -> Option<Self> {
+                self.get().checked_add(1).and_then(<$nz>::new)
+            }
+
+            #[inline]
+            fn get(self) -> usize {
+                <$nz>::get(self) as usize
+            }
+
+            #[inline]
+            fn from_usize(value: usize) -> Option<Self> {
+                <$prim>::try_from(value).ok().and_then(<$nz>::new)
+            }
+        }
+    };
+}
+
+impl_gen_num!(NonZeroU16, u16);
+impl_gen_num!(NonZeroU32, u32);
+impl_gen_num!(NonZeroU64, u64);
+impl_gen_num!(NonZeroUsize, usize);
+
+/// A slot-index backing store: an unsigned integer type a `GenerationalArena` uses to address its
+/// slots and to link its free list.
+///
+/// Parameterizing `GenerationIndex`/`Slot`/`GenerationalArena` over this trait lets callers shrink
+/// the free-list linkage (an `Option<I>` in every `Free` slot) from the default `usize` down to,
+/// e.g., `u32`, on top of whatever [`GenNum`] already saves on the generation side. Every slot
+/// position below `ELEMENTS_COUNT` must fit in `I`; an arena whose highest slot position
+/// (`ELEMENTS_COUNT - 1`) doesn't fit panics on construction.
+pub trait SlotNum: Copy + Eq + Ord + std::fmt::Debug {
+    /// Converts a slot position into this type, or `None` if it doesn't fit.
+    fn from_usize(value: usize) -> Option<Self>;
+
+    /// Converts this slot position back into a `usize` for indexing into the backing `Vec`.
+    fn to_usize(self) -> usize;
+}
+
+macro_rules! impl_slot_num {
+    ($prim:ty) => {
+        impl SlotNum for $prim {
+            #[inline]
+            fn from_usize(value: usize) -> Option<Self> {
+                <$prim>::try_from(value).ok()
+            }
+
+            #[inline]
+            fn to_usize(self) -> usize {
+                self as usize
+            }
+        }
+    };
+}
+
+impl_slot_num!(u16);
+impl_slot_num!(u32);
+impl_slot_num!(u64);
+impl_slot_num!(usize);
 
 /// A `GenerationIndex` is a composite key into the contiguous block of memory which is managed
 /// by our `GenerationalArena`. It is an `index` into the contiguous block of memory with an
 /// associated `generation` information.
+///
+/// `G` is the backing integer type for the generation counter (see [`GenNum`]) and must match the
+/// `GenerationalArena` the index came from. `I` is the backing integer type for the slot index
+/// (see [`SlotNum`]) and likewise must match.
 #[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
-pub struct GenerationIndex {
-    index: SlotIndex,
-    generation: GenerationCounter,
+pub struct GenerationIndex<G: GenNum = NonZeroUsize, I: SlotNum = usize> {
+    index: I,
+    generation: GenerationCounter<G>,
+}
+
+impl<G: GenNum, I: SlotNum> GenerationIndex<G, I> {
+    /// Packs this `GenerationIndex` into a single `u64`, with `index` in the low 32 bits and
+    /// `generation` in the high 32 bits.
+    ///
+    /// This is a fixed 32/32 split chosen for a stable, arena-agnostic encoding: it does not
+    /// validate `index` against any particular arena's `ELEMENTS_COUNT`, since that check only
+    /// makes sense once the bits are handed back to an arena (e.g. via [`Self::from_bits`] and
+    /// then a lookup). Useful for crossing FFI boundaries or storing indices in external
+    /// containers.
+    ///
+    /// With the default `G`/`I` (`NonZeroUsize`/`usize` on a 32-bit-or-narrower platform) neither
+    /// half can actually exceed 32 bits. But `G`/`I` can be instantiated as wide as `NonZeroU64`/
+    /// `u64`, and on a 64-bit platform `usize` itself is 64 bits, so `to_bits` returns `None`
+    /// instead of silently truncating whenever `index` or `generation` doesn't fit in 32 bits.
+    #[inline]
+    pub fn to_bits(self) -> Option<u64> {
+        let index = u32::try_from(self.index.to_usize()).ok()? as u64;
+        let generation = u32::try_from(self.generation.0.get()).ok()? as u64;
+        Some((generation << 32) | index)
+    }
+
+    /// Unpacks a `GenerationIndex` previously produced by [`Self::to_bits`].
+    ///
+    /// Returns `None` if the decoded generation is zero or does not fit `G`, since a generation
+    /// is never valid at zero, or if the decoded index does not fit `I`.
+    #[inline]
+    pub fn from_bits(bits: u64) -> Option<GenerationIndex<G, I>> {
+        let index = (bits & u32::MAX as u64) as usize;
+        let generation = (bits >> 32) as usize;
+
+        let index = I::from_usize(index)?;
+        G::from_usize(generation).map(|generation| GenerationIndex {
+            index,
+            generation: GenerationCounter(generation),
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<G: GenNum + serde::Serialize, I: SlotNum + serde::Serialize> serde::Serialize
+    for GenerationIndex<G, I>
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("GenerationIndex", 2)?;
+        state.serialize_field("index", &self.index)?;
+        state.serialize_field("generation", &self.generation)?;
+        state.end()
+    }
 }
 
-type SlotIndex = usize;
+#[cfg(feature = "serde")]
+impl<'de, G: GenNum + serde::Deserialize<'de>, I: SlotNum + serde::Deserialize<'de>>
+    serde::Deserialize<'de> for GenerationIndex<G, I>
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        #[serde(bound(deserialize = "G: serde::Deserialize<'de>, I: SlotNum + serde::Deserialize<'de>"))]
+        struct Raw<G: GenNum, I: SlotNum> {
+            index: I,
+            generation: GenerationCounter<G>,
+        }
+
+        Raw::<G, I>::deserialize(deserializer).map(|raw| GenerationIndex {
+            index: raw.index,
+            generation: raw.generation,
+        })
+    }
+}
 
 #[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
-struct GenerationCounter(NonZeroUsize);
+struct GenerationCounter<G: GenNum>(G);
+
+impl<G: GenNum> GenerationCounter<G> {
+    fn new() -> GenerationCounter<G> {
+        GenerationCounter(G::new())
+    }
+}
 
-impl GenerationCounter {
-    fn new() -> GenerationCounter {
-        GenerationCounter(NonZeroUsize::new(1).unwrap())
+#[cfg(feature = "serde")]
+impl<G: GenNum + serde::Serialize> serde::Serialize for GenerationCounter<G> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.serialize(serializer)
     }
+}
 
-    fn next_generation(&mut self) {
-        self.0 = NonZeroUsize::new(
-            self.0
-                .get()
-                .checked_add(1)
-                .expect("exhausted generation counter"),
-        )
-        .unwrap();
+#[cfg(feature = "serde")]
+impl<'de, G: GenNum + serde::Deserialize<'de>> serde::Deserialize<'de> for GenerationCounter<G> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        G::deserialize(deserializer).map(GenerationCounter)
     }
 }
 
-/// A `Slot<T>` represents a region in the arena that is large enough to hold exactly one of `T`.
+/// Controls what a `GenerationalArena` does when its generation counter (backed by `G`, see
+/// [`GenNum`]) is exhausted by a removal that would otherwise bump it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GenerationOverflow {
+    /// Panic. This is the default: a wrapped-around generation could alias a stale
+    /// `GenerationIndex`, and for most callers that's a bug worth crashing on.
+    #[default]
+    Panic,
+    /// Retire the slot that collides with the saturated counter, instead of panicking: that one
+    /// freed slot is left `Free` but is *not* linked back into the free list, so it can never be
+    /// reused, permanently costing the arena that one slot of capacity. Once the counter is
+    /// capped at its maximum, every other slot keeps working as before — `self.generation` is
+    /// arena-wide, not per-slot, so "colliding" means the removed slot's own stored generation
+    /// was already at that maximum; any slot whose stored generation is still below it is safe
+    /// to relink and reuse at the capped value, since that's still strictly newer.
+    Retire,
+}
+
+/// A `Slot<T, G, I>` represents a region in the arena that is large enough to hold exactly one of
+/// `T`, tagged with a generation backed by `G` (see [`GenNum`]) and linked via a slot index backed
+/// by `I` (see [`SlotNum`]).
 ///
 /// A `Slot` can be either:
 ///
 /// - `Free`: no previous data occupied this slot, can be trivially inserted in-place.
 /// - `Occupied`: the slot is already occupied and must be freed before new data can be inserted.
 #[derive(Debug, Clone)]
-enum Slot<T> {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Slot<T, G: GenNum = NonZeroUsize, I: SlotNum = usize> {
     Free {
-        next_free: Option<SlotIndex>,
+        next_free: Option<I>,
     },
     Occupied {
-        generation: GenerationCounter,
+        generation: GenerationCounter<G>,
         value: T,
     },
 }
@@ -56,6 +261,15 @@ enum Slot<T> {
 /// of the inserted data. When a slot is freed, the generation is incremented to differentiate
 /// between data inserted at different times. The arena is allocated on the heap.
 ///
+/// `G` picks the backing integer type for the per-slot generation counter (see [`GenNum`]),
+/// defaulting to `NonZeroUsize`. A narrower `G`, e.g. `NonZeroU16`, shrinks `size_of::<Slot<T,
+/// G>>()` at the cost of a smaller generation count before the counter is considered exhausted.
+///
+/// `I` picks the backing integer type for the slot index and free-list linkage (see [`SlotNum`]),
+/// defaulting to `usize`. A narrower `I`, e.g. `u32`, shrinks the `Option<I>` carried by every
+/// `Free` slot; the highest slot position, `ELEMENTS_COUNT - 1`, must fit in `I`, or construction
+/// panics.
+///
 /// Advantages:
 ///
 /// - Reduce likelihood of free entity indices (can reuse existing indicies).
@@ -65,23 +279,31 @@ enum Slot<T> {
 /// Disadvantages:
 ///
 /// - Memory bloat due to unoccupied `Free` slots.
+// `#[derive(Clone)]` generates `impl<T: Clone, const ELEMENTS_COUNT: usize, G: Clone + GenNum, I:
+// Clone + SlotNum> Clone for GenerationalArena<T, ELEMENTS_COUNT, G, I>` on its own — the arena
+// itself carries no `T: Clone` bound, so it can hold any `Sized` `T` (file handles, channels,
+// `Box<dyn Trait>`, ...) and is only `Clone` when `T` happens to be.
 #[derive(Debug, Clone)]
-pub struct GenerationalArena<T, const ELEMENTS_COUNT: usize>
-where
-    T: Clone,
-{
-    items: Vec<Slot<T>>,
-    free_list_head: Option<SlotIndex>,
-    generation: GenerationCounter,
+pub struct GenerationalArena<T, const ELEMENTS_COUNT: usize, G: GenNum = NonZeroUsize, I: SlotNum = usize> {
+    items: Vec<Slot<T, G, I>>,
+    free_list_head: Option<I>,
+    generation: GenerationCounter<G>,
     len: usize,
+    overflow: GenerationOverflow,
 }
 
-impl<T, const ELEMENTS_COUNT: usize> GenerationalArena<T, ELEMENTS_COUNT>
-where
-    T: Clone,
-{
+impl<T, const ELEMENTS_COUNT: usize, G: GenNum, I: SlotNum> GenerationalArena<T, ELEMENTS_COUNT, G, I> {
     #[inline]
-    pub fn new() -> GenerationalArena<T, ELEMENTS_COUNT> {
+    pub fn new() -> GenerationalArena<T, ELEMENTS_COUNT, G, I> {
+        Self::new_with_overflow_policy(GenerationOverflow::default())
+    }
+
+    /// Like [`Self::new`], but lets the caller pick what happens when the generation counter is
+    /// exhausted (see [`GenerationOverflow`]) instead of always panicking.
+    #[inline]
+    pub fn new_with_overflow_policy(
+        overflow: GenerationOverflow,
+    ) -> GenerationalArena<T, ELEMENTS_COUNT, G, I> {
         assert!(ELEMENTS_COUNT > 0);
 
         let mut arena = GenerationalArena {
@@ -89,12 +311,36 @@ where
             free_list_head: None,
             generation: GenerationCounter::new(),
             len: 0,
+            overflow,
         };
 
         arena.initialize_slots();
         arena
     }
 
+    /// Advances `self.generation` to the next value on behalf of a slot previously stamped with
+    /// `freed_generation`, applying `self.overflow` if the counter is exhausted. Returns `true` if
+    /// the freed slot should be linked back into the free list, or `false` if it must stay
+    /// unlinked (retired) because reusing it could alias `freed_generation`.
+    ///
+    /// Once the counter is capped at its maximum under [`GenerationOverflow::Retire`], only the
+    /// slot whose own `freed_generation` already *is* that maximum is unsafe to reuse (its next
+    /// occupant would be stamped with the same capped value, aliasing the value a stale handle
+    /// still holds). Every other slot's `freed_generation` is strictly below the cap, so handing
+    /// it the capped value on reuse is still a strict advance and remains safe.
+    fn advance_generation(&mut self, freed_generation: GenerationCounter<G>) -> bool {
+        match self.generation.0.next() {
+            Some(next) => {
+                self.generation.0 = next;
+                true
+            }
+            None => match self.overflow {
+                GenerationOverflow::Panic => panic!("exhausted generation counter"),
+                GenerationOverflow::Retire => freed_generation < self.generation,
+            },
+        }
+    }
+
     fn initialize_slots(&mut self) {
         self.items.extend((0..ELEMENTS_COUNT).map(|i| {
             // The last slot's `next_free == None` indicates that we have no more free space.
@@ -102,23 +348,28 @@ where
                 Slot::Free { next_free: None }
             } else {
                 Slot::Free {
-                    next_free: Some(i + 1),
+                    next_free: Some(Self::slot_index(i + 1)),
                 }
             }
         }));
 
-        self.free_list_head = Some(0);
+        self.free_list_head = Some(Self::slot_index(0));
+    }
+
+    /// Converts a `usize` slot position into `I`, panicking if it doesn't fit `I`.
+    fn slot_index(i: usize) -> I {
+        I::from_usize(i).expect("ELEMENTS_COUNT exceeds the range of the slot index type")
     }
 
     #[inline]
-    pub fn try_insert(&mut self, value: T) -> Result<GenerationIndex, T> {
+    pub fn try_insert(&mut self, value: T) -> Result<GenerationIndex<G, I>, T> {
         match self.free_list_head {
             None => {
                 // We've exceeded our full capacity, so we return ownership of `T` back to the
                 // caller.
                 Err(value)
             }
-            Some(i) => match self.items[i] {
+            Some(i) => match self.items[i.to_usize()] {
                 Slot::Occupied { .. } => {
                     // This cannot happen, unless the free list is corrupted.
                     panic!("corrupt free list");
@@ -132,7 +383,7 @@ where
                         generation: self.generation,
                     };
 
-                    self.items[gen_index.index] = Slot::Occupied {
+                    self.items[gen_index.index.to_usize()] = Slot::Occupied {
                         generation: self.generation,
                         value,
                     };
@@ -144,21 +395,23 @@ where
     }
 
     #[inline]
-    pub fn remove(&mut self, generation_index: GenerationIndex) -> Option<T> {
-        assert!(generation_index.index < ELEMENTS_COUNT);
+    pub fn remove(&mut self, generation_index: GenerationIndex<G, I>) -> Option<T> {
+        let index = generation_index.index.to_usize();
+        assert!(index < ELEMENTS_COUNT);
 
-        match self.items[generation_index.index] {
+        match self.items[index] {
             Slot::Occupied { generation, .. } if generation_index.generation == generation => {
                 let slot = std::mem::replace(
-                    &mut self.items[generation_index.index],
+                    &mut self.items[index],
                     Slot::Free {
                         next_free: self.free_list_head,
                     },
                 );
 
-                self.generation.next_generation();
-                self.free_list_head = Some(generation_index.index);
                 self.len -= 1;
+                if self.advance_generation(generation) {
+                    self.free_list_head = Some(generation_index.index);
+                }
 
                 match slot {
                     Slot::Occupied {
@@ -172,6 +425,77 @@ where
         }
     }
 
+    /// Inserts `value` at the slot addressed by `index`, setting that slot's generation to
+    /// `index.generation` and splicing the slot out of the free list if it was `Free`. If the
+    /// slot was already `Occupied`, the previous value is evicted and returned.
+    ///
+    /// This is meant for rebuilding an arena from a previously serialized world where every
+    /// entity kept its `GenerationIndex` (see thunderdome#30), so `self.generation` is also
+    /// advanced past `index.generation` to guarantee future fresh inserts never collide with a
+    /// restored generation.
+    #[inline]
+    pub fn insert_at(&mut self, index: GenerationIndex<G, I>, value: T) -> Option<T> {
+        self.insert_at_slot(index, value).1
+    }
+
+    fn insert_at_slot(
+        &mut self,
+        index: GenerationIndex<G, I>,
+        value: T,
+    ) -> (GenerationIndex<G, I>, Option<T>) {
+        let slot_index = index.index.to_usize();
+        assert!(slot_index < ELEMENTS_COUNT);
+
+        let old_slot = std::mem::replace(
+            &mut self.items[slot_index],
+            Slot::Occupied {
+                generation: index.generation,
+                value,
+            },
+        );
+
+        let old_value = match old_slot {
+            Slot::Free { next_free } => {
+                self.unlink_free_slot(index.index, next_free);
+                self.len += 1;
+                None
+            }
+            Slot::Occupied { value, .. } => Some(value),
+        };
+
+        // Ensure fresh inserts from here on are stamped with a generation strictly newer than the
+        // one we just restored, so a stale handle from before the save can never match again.
+        // The return value is irrelevant here: this slot is already occupied by `index`, not
+        // linked into the free list, so there's nothing to retire.
+        if index.generation >= self.generation {
+            self.generation = index.generation;
+            self.advance_generation(index.generation);
+        }
+
+        (index, old_value)
+    }
+
+    /// Removes `target` from the free list, wherever it sits in the chain, given the `next_free`
+    /// it currently points to.
+    fn unlink_free_slot(&mut self, target: I, target_next: Option<I>) {
+        if self.free_list_head == Some(target) {
+            self.free_list_head = target_next;
+            return;
+        }
+
+        let mut cursor = self.free_list_head;
+        while let Some(i) = cursor {
+            match &mut self.items[i.to_usize()] {
+                Slot::Free { next_free } if *next_free == Some(target) => {
+                    *next_free = target_next;
+                    return;
+                }
+                Slot::Free { next_free } => cursor = *next_free,
+                Slot::Occupied { .. } => panic!("corrupt free list"),
+            }
+        }
+    }
+
     #[inline]
     pub fn capacity(&self) -> usize {
         self.len
@@ -181,8 +505,8 @@ where
     // mutability of `self`, and transitively, `generation` and `value`. It looks like Higher-Kinded
     // Types (HKT) is needed in order to be parametric over the mutability of `self`.
     #[inline]
-    pub fn get(&self, generation_index: GenerationIndex) -> Option<&T> {
-        match self.items.get(generation_index.index) {
+    pub fn get(&self, generation_index: GenerationIndex<G, I>) -> Option<&T> {
+        match self.items.get(generation_index.index.to_usize()) {
             Some(Slot::Occupied { generation, value })
                 if *generation == generation_index.generation =>
             {
@@ -193,8 +517,8 @@ where
     }
 
     #[inline]
-    pub fn get_mut(&mut self, generation_index: GenerationIndex) -> Option<&mut T> {
-        match self.items.get_mut(generation_index.index) {
+    pub fn get_mut(&mut self, generation_index: GenerationIndex<G, I>) -> Option<&mut T> {
+        match self.items.get_mut(generation_index.index.to_usize()) {
             Some(Slot::Occupied { generation, value })
                 if *generation == generation_index.generation =>
             {
@@ -205,9 +529,503 @@ where
     }
 
     #[inline]
-    pub fn contains(&self, generation_index: GenerationIndex) -> bool {
+    pub fn contains(&self, generation_index: GenerationIndex<G, I>) -> bool {
         self.get(generation_index).is_some()
     }
+
+    /// Returns two mutable references into the arena for `a` and `b` simultaneously, which
+    /// `get_mut` alone cannot express because the borrow checker can't see that two different
+    /// slots don't alias.
+    ///
+    /// Each in-range `GenerationIndex` is validated independently, and is `None` in the result if
+    /// it does not currently point at a live value. If either `index` is out of range for this
+    /// arena (e.g. one produced by [`GenerationIndex::from_bits`], which does not validate
+    /// against any particular arena), both positions in the result are `None`. If `a` and `b`
+    /// address the same slot, both positions in the result are `None` rather than aliasing a
+    /// `&mut T` twice.
+    #[inline]
+    pub fn get2_mut(
+        &mut self,
+        a: GenerationIndex<G, I>,
+        b: GenerationIndex<G, I>,
+    ) -> (Option<&mut T>, Option<&mut T>) {
+        if a.index == b.index {
+            return (None, None);
+        }
+
+        let (lo, hi) = if a.index < b.index { (a, b) } else { (b, a) };
+        let hi_index = hi.index.to_usize();
+        let lo_index = lo.index.to_usize();
+
+        if hi_index >= self.items.len() {
+            return (None, None);
+        }
+
+        let (lo_items, hi_items) = self.items.split_at_mut(hi_index);
+
+        let lo_value = match &mut lo_items[lo_index] {
+            Slot::Occupied { generation, value } if *generation == lo.generation => Some(value),
+            _ => None,
+        };
+        let hi_value = match &mut hi_items[0] {
+            Slot::Occupied { generation, value } if *generation == hi.generation => Some(value),
+            _ => None,
+        };
+
+        if a.index < b.index {
+            (lo_value, hi_value)
+        } else {
+            (hi_value, lo_value)
+        }
+    }
+
+    /// Retains only the elements for which `f` returns `true`, removing the rest with the same
+    /// free-list-prepend and generation-bump bookkeeping as [`Self::remove`].
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(GenerationIndex<G, I>, &mut T) -> bool,
+    {
+        for index in 0..self.items.len() {
+            let keep = match &mut self.items[index] {
+                Slot::Occupied { generation, value } => f(
+                    GenerationIndex {
+                        index: Self::slot_index(index),
+                        generation: *generation,
+                    },
+                    value,
+                ),
+                Slot::Free { .. } => continue,
+            };
+
+            if !keep {
+                let slot = std::mem::replace(
+                    &mut self.items[index],
+                    Slot::Free {
+                        next_free: self.free_list_head,
+                    },
+                );
+                let freed_generation = match slot {
+                    Slot::Occupied { generation, .. } => generation,
+                    Slot::Free { .. } => unreachable!(),
+                };
+
+                self.len -= 1;
+                if self.advance_generation(freed_generation) {
+                    self.free_list_head = Some(Self::slot_index(index));
+                }
+            }
+        }
+    }
+
+    /// Returns an iterator over the live `(GenerationIndex, &T)` pairs in the arena, in slot
+    /// order. `Free` slots are skipped.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T, ELEMENTS_COUNT, G, I> {
+        Iter {
+            inner: self.items.iter().enumerate(),
+            remaining: self.len,
+        }
+    }
+
+    /// Returns an iterator over the live `(GenerationIndex, &mut T)` pairs in the arena, in slot
+    /// order. `Free` slots are skipped.
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, ELEMENTS_COUNT, G, I> {
+        IterMut {
+            inner: self.items.iter_mut().enumerate(),
+            remaining: self.len,
+        }
+    }
+
+    /// Removes every live element from the arena, yielding each `(GenerationIndex, T)` pair as it
+    /// is removed. Each drained slot is reset to `Free` and its generation is bumped, so indices
+    /// obtained before draining stay invalid afterwards.
+    ///
+    /// If the returned `Drain` is dropped before being fully consumed, the remaining elements are
+    /// still drained so the arena ends up empty.
+    #[inline]
+    pub fn drain(&mut self) -> Drain<'_, T, ELEMENTS_COUNT, G, I> {
+        Drain {
+            arena: self,
+            cursor: 0,
+        }
+    }
+}
+
+impl<T, const ELEMENTS_COUNT: usize, G: GenNum, I: SlotNum> Default
+    for GenerationalArena<T, ELEMENTS_COUNT, G, I>
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, const ELEMENTS_COUNT: usize, G, I> serde::Serialize
+    for GenerationalArena<T, ELEMENTS_COUNT, G, I>
+where
+    T: serde::Serialize,
+    G: GenNum + serde::Serialize,
+    I: SlotNum + serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        // `free_list_head` and each `Free` slot's `next_free` are reconstructed on deserialize,
+        // so only the occupancy/values and the generation counter need to cross the wire.
+        let mut state = serializer.serialize_struct("GenerationalArena", 2)?;
+        state.serialize_field("items", &self.items)?;
+        state.serialize_field("generation", &self.generation)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, const ELEMENTS_COUNT: usize, G, I> serde::Deserialize<'de>
+    for GenerationalArena<T, ELEMENTS_COUNT, G, I>
+where
+    T: serde::Deserialize<'de>,
+    G: GenNum + serde::Deserialize<'de>,
+    I: SlotNum + serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        #[serde(bound(
+            deserialize = "T: serde::Deserialize<'de>, G: GenNum + serde::Deserialize<'de>, I: SlotNum + serde::Deserialize<'de>"
+        ))]
+        struct Raw<T, G: GenNum, I: SlotNum> {
+            items: Vec<Slot<T, G, I>>,
+            generation: GenerationCounter<G>,
+        }
+
+        let raw = Raw::<T, G, I>::deserialize(deserializer)?;
+
+        if raw.items.len() != ELEMENTS_COUNT {
+            return Err(serde::de::Error::custom(format!(
+                "expected {} slots for this arena's ELEMENTS_COUNT, found {}",
+                ELEMENTS_COUNT,
+                raw.items.len()
+            )));
+        }
+
+        let mut items = raw.items;
+        let mut free_list_head = None;
+        let mut len = 0;
+
+        // Rebuild the free list deterministically from slot occupancy, walking in descending
+        // index order so the resulting chain allocates in ascending index order afterwards, just
+        // like a freshly initialized arena. We deliberately ignore whatever `next_free` chaining
+        // was serialized, so allocation order after a save/load cycle only depends on occupancy,
+        // not on the deletion history that produced it.
+        for index in (0..items.len()).rev() {
+            match &mut items[index] {
+                Slot::Occupied { .. } => len += 1,
+                Slot::Free { next_free } => {
+                    *next_free = free_list_head;
+                    free_list_head = Some(GenerationalArena::<T, ELEMENTS_COUNT, G, I>::slot_index(index));
+                }
+            }
+        }
+
+        // Advance the generation counter past every occupied slot's generation, so inserting into
+        // a freed slot after loading can never stamp a generation that collides with a still-live
+        // handle from before the save.
+        let mut generation = raw.generation;
+        for slot in &items {
+            if let Slot::Occupied {
+                generation: occupant,
+                ..
+            } = slot
+            {
+                if *occupant >= generation {
+                    generation = *occupant;
+                    generation.0 = generation.0.next().expect("exhausted generation counter");
+                }
+            }
+        }
+
+        Ok(GenerationalArena {
+            items,
+            free_list_head,
+            generation,
+            len,
+            // The overflow policy is a runtime behavior choice, not arena data, so it doesn't
+            // round-trip through serde any more than `free_list_head` does: a deserialized arena
+            // always starts out with the default policy.
+            overflow: GenerationOverflow::default(),
+        })
+    }
+}
+
+impl<T, const ELEMENTS_COUNT: usize, G: GenNum, I: SlotNum> IntoIterator
+    for GenerationalArena<T, ELEMENTS_COUNT, G, I>
+{
+    type Item = (GenerationIndex<G, I>, T);
+    type IntoIter = IntoIter<T, ELEMENTS_COUNT, G, I>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            len: self.len,
+            inner: self.items.into_iter().enumerate(),
+        }
+    }
+}
+
+impl<'a, T, const ELEMENTS_COUNT: usize, G: GenNum, I: SlotNum> IntoIterator
+    for &'a GenerationalArena<T, ELEMENTS_COUNT, G, I>
+{
+    type Item = (GenerationIndex<G, I>, &'a T);
+    type IntoIter = Iter<'a, T, ELEMENTS_COUNT, G, I>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const ELEMENTS_COUNT: usize, G: GenNum, I: SlotNum> IntoIterator
+    for &'a mut GenerationalArena<T, ELEMENTS_COUNT, G, I>
+{
+    type Item = (GenerationIndex<G, I>, &'a mut T);
+    type IntoIter = IterMut<'a, T, ELEMENTS_COUNT, G, I>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Converts a `usize` slot position from `.enumerate()` into `I`, panicking if it doesn't fit `I`
+/// (mirrors `GenerationalArena::slot_index`).
+#[inline]
+fn iter_slot_index<I: SlotNum>(index: usize) -> I {
+    I::from_usize(index).expect("ELEMENTS_COUNT exceeds the range of the slot index type")
+}
+
+/// An iterator over `(GenerationIndex, &T)` pairs of the live elements in a `GenerationalArena`.
+///
+/// Created by [`GenerationalArena::iter`].
+#[derive(Debug)]
+pub struct Iter<'a, T, const ELEMENTS_COUNT: usize, G: GenNum = NonZeroUsize, I: SlotNum = usize> {
+    inner: std::iter::Enumerate<std::slice::Iter<'a, Slot<T, G, I>>>,
+    remaining: usize,
+}
+
+impl<'a, T, const ELEMENTS_COUNT: usize, G: GenNum, I: SlotNum> Iterator
+    for Iter<'a, T, ELEMENTS_COUNT, G, I>
+{
+    type Item = (GenerationIndex<G, I>, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (index, slot) in &mut self.inner {
+            if let Slot::Occupied { generation, value } = slot {
+                self.remaining -= 1;
+                return Some((
+                    GenerationIndex {
+                        index: iter_slot_index(index),
+                        generation: *generation,
+                    },
+                    value,
+                ));
+            }
+        }
+
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T, const ELEMENTS_COUNT: usize, G: GenNum, I: SlotNum> ExactSizeIterator
+    for Iter<'a, T, ELEMENTS_COUNT, G, I>
+{
+}
+impl<'a, T, const ELEMENTS_COUNT: usize, G: GenNum, I: SlotNum> FusedIterator
+    for Iter<'a, T, ELEMENTS_COUNT, G, I>
+{
+}
+
+/// An iterator over `(GenerationIndex, &mut T)` pairs of the live elements in a
+/// `GenerationalArena`.
+///
+/// Created by [`GenerationalArena::iter_mut`].
+#[derive(Debug)]
+pub struct IterMut<'a, T, const ELEMENTS_COUNT: usize, G: GenNum = NonZeroUsize, I: SlotNum = usize>
+{
+    inner: std::iter::Enumerate<std::slice::IterMut<'a, Slot<T, G, I>>>,
+    remaining: usize,
+}
+
+impl<'a, T, const ELEMENTS_COUNT: usize, G: GenNum, I: SlotNum> Iterator
+    for IterMut<'a, T, ELEMENTS_COUNT, G, I>
+{
+    type Item = (GenerationIndex<G, I>, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (index, slot) in &mut self.inner {
+            if let Slot::Occupied { generation, value } = slot {
+                self.remaining -= 1;
+                return Some((
+                    GenerationIndex {
+                        index: iter_slot_index(index),
+                        generation: *generation,
+                    },
+                    value,
+                ));
+            }
+        }
+
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T, const ELEMENTS_COUNT: usize, G: GenNum, I: SlotNum> ExactSizeIterator
+    for IterMut<'a, T, ELEMENTS_COUNT, G, I>
+{
+}
+impl<'a, T, const ELEMENTS_COUNT: usize, G: GenNum, I: SlotNum> FusedIterator
+    for IterMut<'a, T, ELEMENTS_COUNT, G, I>
+{
+}
+
+/// An owning iterator over `(GenerationIndex, T)` pairs of the live elements in a
+/// `GenerationalArena`.
+///
+/// Created by the `IntoIterator` impl on `GenerationalArena`.
+#[derive(Debug)]
+pub struct IntoIter<T, const ELEMENTS_COUNT: usize, G: GenNum = NonZeroUsize, I: SlotNum = usize> {
+    inner: std::iter::Enumerate<std::vec::IntoIter<Slot<T, G, I>>>,
+    len: usize,
+}
+
+impl<T, const ELEMENTS_COUNT: usize, G: GenNum, I: SlotNum> Iterator
+    for IntoIter<T, ELEMENTS_COUNT, G, I>
+{
+    type Item = (GenerationIndex<G, I>, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (index, slot) in &mut self.inner {
+            if let Slot::Occupied { generation, value } = slot {
+                self.len -= 1;
+                return Some((
+                    GenerationIndex {
+                        index: iter_slot_index(index),
+                        generation,
+                    },
+                    value,
+                ));
+            }
+        }
+
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T, const ELEMENTS_COUNT: usize, G: GenNum, I: SlotNum> ExactSizeIterator
+    for IntoIter<T, ELEMENTS_COUNT, G, I>
+{
+}
+impl<T, const ELEMENTS_COUNT: usize, G: GenNum, I: SlotNum> FusedIterator
+    for IntoIter<T, ELEMENTS_COUNT, G, I>
+{
+}
+
+/// A draining iterator over `(GenerationIndex, T)` pairs, removing every live element from the
+/// arena as it is yielded.
+///
+/// Created by [`GenerationalArena::drain`].
+#[derive(Debug)]
+pub struct Drain<'a, T, const ELEMENTS_COUNT: usize, G: GenNum = NonZeroUsize, I: SlotNum = usize> {
+    arena: &'a mut GenerationalArena<T, ELEMENTS_COUNT, G, I>,
+    cursor: usize,
+}
+
+impl<'a, T, const ELEMENTS_COUNT: usize, G: GenNum, I: SlotNum> Iterator
+    for Drain<'a, T, ELEMENTS_COUNT, G, I>
+{
+    type Item = (GenerationIndex<G, I>, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.cursor < self.arena.items.len() {
+            let index = self.cursor;
+            self.cursor += 1;
+
+            match &self.arena.items[index] {
+                Slot::Occupied { generation, .. } => {
+                    let generation = *generation;
+                    let slot_index = iter_slot_index(index);
+
+                    let slot = std::mem::replace(
+                        &mut self.arena.items[index],
+                        Slot::Free {
+                            next_free: self.arena.free_list_head,
+                        },
+                    );
+                    self.arena.len -= 1;
+                    if self.arena.advance_generation(generation) {
+                        self.arena.free_list_head = Some(slot_index);
+                    }
+
+                    return match slot {
+                        Slot::Occupied { value, .. } => Some((
+                            GenerationIndex {
+                                index: slot_index,
+                                generation,
+                            },
+                            value,
+                        )),
+                        _ => unreachable!(),
+                    };
+                }
+                Slot::Free { .. } => continue,
+            }
+        }
+
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.arena.len, Some(self.arena.len))
+    }
+}
+
+impl<'a, T, const ELEMENTS_COUNT: usize, G: GenNum, I: SlotNum> ExactSizeIterator
+    for Drain<'a, T, ELEMENTS_COUNT, G, I>
+{
+}
+impl<'a, T, const ELEMENTS_COUNT: usize, G: GenNum, I: SlotNum> FusedIterator
+    for Drain<'a, T, ELEMENTS_COUNT, G, I>
+{
+}
+
+impl<'a, T, const ELEMENTS_COUNT: usize, G: GenNum, I: SlotNum> Drop for Drain<'a, T, ELEMENTS_COUNT, G, I> {
+    fn drop(&mut self) {
+        // Finish consuming so any elements not yet yielded are still removed, leaving the arena
+        // empty even if the caller drops us early.
+        for _ in self.by_ref() {}
+    }
 }
 
 #[cfg(test)]
@@ -283,4 +1101,378 @@ mod tests {
         arena.remove(idx);
         arena.get(idx).unwrap();
     }
+
+    #[test]
+    fn generation_index_bits_round_trip() {
+        let mut arena = GenerationalArena::<u32, 1>::new();
+        let i = arena.try_insert(42).unwrap();
+
+        let bits = i.to_bits().unwrap();
+        assert_eq!(GenerationIndex::from_bits(bits), Some(i));
+    }
+
+    #[test]
+    fn to_bits_rejects_values_that_do_not_fit_32_bits() {
+        // `NonZeroU64`/`u64` can represent generations and indices that the 32/32 `to_bits` split
+        // can't, so it must report that instead of silently truncating them.
+        let fits = GenerationIndex::<NonZeroU64, u64>::from_bits(0x0000_0001_0000_0001).unwrap();
+        assert!(fits.to_bits().is_some());
+
+        let generation_too_wide =
+            GenerationIndex::<NonZeroU64, u64>::from_bits(0x0000_0001_0000_0001).unwrap();
+        let generation_too_wide = GenerationIndex {
+            generation: GenerationCounter(NonZeroU64::new(10_000_000_000).unwrap()),
+            ..generation_too_wide
+        };
+        assert!(generation_too_wide.to_bits().is_none());
+
+        let index_too_wide = GenerationIndex {
+            index: 5_000_000_000u64,
+            ..fits
+        };
+        assert!(index_too_wide.to_bits().is_none());
+    }
+
+    #[test]
+    fn generation_index_from_bits_rejects_zero_generation() {
+        // A zero generation is never valid, since `GenerationCounter` starts at 1.
+        assert_eq!(GenerationIndex::<NonZeroUsize>::from_bits(0), None);
+        assert_eq!(
+            GenerationIndex::<NonZeroUsize>::from_bits(0x0000_0000_0000_002A),
+            None
+        );
+    }
+
+    #[test]
+    fn insert_at_restores_a_saved_index() {
+        let mut arena = GenerationalArena::<u32, 1>::new();
+        let saved = GenerationIndex::from_bits(0x0000_0005_0000_0000).unwrap();
+
+        assert_eq!(arena.insert_at(saved, 42), None);
+        assert_eq!(*arena.get(saved).unwrap(), 42);
+    }
+
+    #[test]
+    fn insert_at_evicts_existing_occupant() {
+        let mut arena = GenerationalArena::<u32, 1>::new();
+        let i = arena.try_insert(42).unwrap();
+
+        assert_eq!(arena.insert_at(i, 43), Some(42));
+        assert_eq!(*arena.get(i).unwrap(), 43);
+    }
+
+    #[test]
+    fn insert_at_splices_slot_out_of_free_list() {
+        let mut arena = GenerationalArena::<u32, 3>::new();
+        let a = arena.try_insert(1).unwrap();
+        let b = arena.try_insert(2).unwrap();
+        let c = arena.try_insert(3).unwrap();
+        arena.remove(a).unwrap();
+        arena.remove(b).unwrap();
+        arena.remove(c).unwrap();
+
+        // `b`'s slot sits in the middle of the free list; restoring it must not corrupt the
+        // chain for the remaining free slots.
+        let restored = GenerationIndex::from_bits(0x0000_0009_0000_0001).unwrap();
+        assert_eq!(arena.insert_at(restored, 99), None);
+
+        let x = arena.try_insert(100).unwrap();
+        let y = arena.try_insert(200).unwrap();
+        assert_ne!(x, y);
+        assert_eq!(*arena.get(x).unwrap(), 100);
+        assert_eq!(*arena.get(y).unwrap(), 200);
+        assert!(arena.try_insert(300).is_err());
+    }
+
+    #[test]
+    fn insert_at_advances_generation_past_restored_value() {
+        let mut arena = GenerationalArena::<u32, 2>::new();
+        let high_generation = GenerationIndex::from_bits(0x0000_0064_0000_0000).unwrap();
+        arena.insert_at(high_generation, 1);
+
+        let fresh = arena.try_insert(2).unwrap();
+        assert_ne!(fresh, high_generation);
+        assert!(fresh.generation > high_generation.generation);
+    }
+
+    #[test]
+    fn iter_skips_free_slots() {
+        let mut arena = GenerationalArena::<u32, 3>::new();
+        let a = arena.try_insert(1).unwrap();
+        let _b = arena.try_insert(2).unwrap();
+        let c = arena.try_insert(3).unwrap();
+        arena.remove(_b);
+
+        let mut items: Vec<_> = arena.iter().collect();
+        items.sort_by_key(|(index, _)| *index);
+        assert_eq!(items, vec![(a, &1), (c, &3)]);
+    }
+
+    #[test]
+    fn iter_mut_allows_mutation() {
+        let mut arena = GenerationalArena::<u32, 2>::new();
+        arena.try_insert(1).unwrap();
+        arena.try_insert(2).unwrap();
+
+        for (_, value) in arena.iter_mut() {
+            *value += 10;
+        }
+
+        let values: Vec<_> = arena.iter().map(|(_, value)| *value).collect();
+        assert_eq!(values, vec![11, 12]);
+    }
+
+    #[test]
+    fn into_iter_yields_owned_values() {
+        let mut arena = GenerationalArena::<u32, 2>::new();
+        arena.try_insert(1).unwrap();
+        arena.try_insert(2).unwrap();
+
+        let values: Vec<_> = arena.into_iter().map(|(_, value)| value).collect();
+        assert_eq!(values, vec![1, 2]);
+    }
+
+    #[test]
+    fn drain_empties_arena_and_invalidates_indices() {
+        let mut arena = GenerationalArena::<u32, 2>::new();
+        let a = arena.try_insert(1).unwrap();
+        let b = arena.try_insert(2).unwrap();
+
+        let drained: Vec<_> = arena.drain().collect();
+        assert_eq!(drained, vec![(a, 1), (b, 2)]);
+        assert_eq!(arena.iter().count(), 0);
+        assert!(!arena.contains(a));
+        assert!(!arena.contains(b));
+
+        // The arena should be fully reusable after draining.
+        let c = arena.try_insert(3).unwrap();
+        assert_eq!(*arena.get(c).unwrap(), 3);
+    }
+
+    #[test]
+    fn drain_dropped_early_still_empties_arena() {
+        let mut arena = GenerationalArena::<u32, 3>::new();
+        arena.try_insert(1).unwrap();
+        arena.try_insert(2).unwrap();
+        arena.try_insert(3).unwrap();
+
+        {
+            let mut drain = arena.drain();
+            assert!(drain.next().is_some());
+            // `drain` is dropped here without consuming the rest.
+        }
+
+        assert_eq!(arena.iter().count(), 0);
+    }
+
+    #[test]
+    fn get2_mut_allows_disjoint_mutation() {
+        let mut arena = GenerationalArena::<u32, 2>::new();
+        let a = arena.try_insert(1).unwrap();
+        let b = arena.try_insert(2).unwrap();
+
+        let (x, y) = arena.get2_mut(a, b);
+        *x.unwrap() += 10;
+        *y.unwrap() += 20;
+
+        assert_eq!(*arena.get(a).unwrap(), 11);
+        assert_eq!(*arena.get(b).unwrap(), 22);
+    }
+
+    #[test]
+    fn get2_mut_rejects_aliasing_index() {
+        let mut arena = GenerationalArena::<u32, 1>::new();
+        let a = arena.try_insert(1).unwrap();
+
+        let (x, y) = arena.get2_mut(a, a);
+        assert!(x.is_none());
+        assert!(y.is_none());
+    }
+
+    #[test]
+    fn get2_mut_handles_dead_indices() {
+        let mut arena = GenerationalArena::<u32, 2>::new();
+        let a = arena.try_insert(1).unwrap();
+        let b = arena.try_insert(2).unwrap();
+        arena.remove(b).unwrap();
+
+        let (x, y) = arena.get2_mut(a, b);
+        assert_eq!(x, Some(&mut 1));
+        assert!(y.is_none());
+    }
+
+    #[test]
+    fn get2_mut_rejects_out_of_range_index() {
+        let mut arena = GenerationalArena::<u32, 2>::new();
+        let a = arena.try_insert(1).unwrap();
+        // `from_bits` deliberately does not validate `index` against any arena's
+        // `ELEMENTS_COUNT`, so this is reachable from ordinary (if stale) user data.
+        let out_of_range = GenerationIndex::from_bits(0x0000_0001_0000_0005).unwrap();
+
+        let (x, y) = arena.get2_mut(a, out_of_range);
+        assert!(x.is_none());
+        assert!(y.is_none());
+
+        let (x, y) = arena.get2_mut(out_of_range, a);
+        assert!(x.is_none());
+        assert!(y.is_none());
+    }
+
+    #[test]
+    fn retain_removes_rejected_elements_and_frees_their_slots() {
+        let mut arena = GenerationalArena::<u32, 3>::new();
+        let a = arena.try_insert(1).unwrap();
+        let b = arena.try_insert(2).unwrap();
+        let c = arena.try_insert(3).unwrap();
+
+        arena.retain(|_, value| *value % 2 == 1);
+
+        assert!(arena.contains(a));
+        assert!(!arena.contains(b));
+        assert!(arena.contains(c));
+
+        // The freed slot should be reusable.
+        let d = arena.try_insert(4).unwrap();
+        assert_eq!(*arena.get(d).unwrap(), 4);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_round_trip_preserves_occupancy_and_allocation_order() {
+        let mut arena = GenerationalArena::<u32, 4>::new();
+        let a = arena.try_insert(1).unwrap();
+        let b = arena.try_insert(2).unwrap();
+        let c = arena.try_insert(3).unwrap();
+        let d = arena.try_insert(4).unwrap();
+        // Punch non-adjacent holes so the free list built during deserialization isn't trivially
+        // a single contiguous run.
+        arena.remove(b).unwrap();
+        arena.remove(d).unwrap();
+
+        let json = serde_json::to_string(&arena).unwrap();
+        let mut restored: GenerationalArena<u32, 4> = serde_json::from_str(&json).unwrap();
+
+        assert!(restored.contains(a));
+        assert!(restored.contains(c));
+        assert!(!restored.contains(b));
+        assert!(!restored.contains(d));
+        assert_eq!(*restored.get(a).unwrap(), 1);
+        assert_eq!(*restored.get(c).unwrap(), 3);
+
+        // A freshly initialized arena always allocates in ascending slot order regardless of the
+        // deletion history that freed a slot; the restored arena must reuse its free slots the
+        // same way.
+        let e = restored.try_insert(5).unwrap();
+        let f = restored.try_insert(6).unwrap();
+        assert_eq!(e.index, b.index);
+        assert_eq!(f.index, d.index);
+        assert!(restored.try_insert(7).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_deserialize_rejects_mismatched_slot_count() {
+        let mut arena = GenerationalArena::<u32, 4>::new();
+        arena.try_insert(1).unwrap();
+
+        let json = serde_json::to_string(&arena).unwrap();
+        let result: Result<GenerationalArena<u32, 3>, _> = serde_json::from_str(&json);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn arena_with_narrow_generation_type() {
+        let mut arena = GenerationalArena::<u32, 2, NonZeroU16>::new();
+        let a = arena.try_insert(1).unwrap();
+        let b = arena.try_insert(2).unwrap();
+        assert_eq!(*arena.get(a).unwrap(), 1);
+        assert_eq!(*arena.get(b).unwrap(), 2);
+        assert_eq!(arena.remove(a), Some(1));
+        assert!(!arena.contains(a));
+    }
+
+    #[test]
+    #[should_panic(expected = "exhausted generation counter")]
+    fn generation_overflow_panics_by_default() {
+        let mut arena = GenerationalArena::<u32, 1, NonZeroU16>::new();
+
+        // Drive the single slot's generation counter to exhaustion; the default
+        // `GenerationOverflow::Panic` policy should panic on the remove that overflows it.
+        loop {
+            let index = arena.try_insert(0).unwrap();
+            arena.remove(index);
+        }
+    }
+
+    #[test]
+    fn generation_overflow_retire_permanently_shrinks_capacity() {
+        let mut arena = GenerationalArena::<u32, 1, NonZeroU16>::new_with_overflow_policy(
+            GenerationOverflow::Retire,
+        );
+
+        // Under `Retire`, the remove that overflows the generation counter frees the slot's
+        // storage but leaves it unlinked from the free list, so this loop runs out on its own.
+        while let Ok(index) = arena.try_insert(0) {
+            arena.remove(index);
+        }
+
+        assert!(arena.try_insert(0).is_err());
+    }
+
+    #[test]
+    fn generation_overflow_retire_only_poisons_the_colliding_slot() {
+        let mut arena = GenerationalArena::<u32, 2, NonZeroU16>::new_with_overflow_policy(
+            GenerationOverflow::Retire,
+        );
+
+        // Slot 0 is allocated once and never touched again.
+        let a0 = arena.try_insert(0).unwrap();
+
+        // Exhaust the *shared* generation counter entirely through slot 1's own churn.
+        let mut a1 = arena.try_insert(1).unwrap();
+        loop {
+            arena.remove(a1);
+            match arena.try_insert(1) {
+                Ok(next) => a1 = next,
+                // Slot 1 is the one whose own stamped generation collided with the now-capped
+                // counter, so it alone is retired.
+                Err(_) => break,
+            }
+        }
+
+        // `self.generation` is arena-wide, not per-slot: the counter is saturated regardless of
+        // which slot's remove hit the cap. But slot 0's own stored generation was never anywhere
+        // near that cap, so reusing it at the capped value is still a strict advance and must
+        // keep working.
+        assert_eq!(arena.remove(a0), Some(0));
+        assert!(arena.try_insert(0).is_ok());
+    }
+
+    #[test]
+    fn arena_with_narrow_slot_index_type() {
+        let mut arena = GenerationalArena::<u32, 2, NonZeroUsize, u32>::new();
+        let a = arena.try_insert(1).unwrap();
+        let b = arena.try_insert(2).unwrap();
+        assert_eq!(*arena.get(a).unwrap(), 1);
+        assert_eq!(*arena.get(b).unwrap(), 2);
+        assert_eq!(arena.remove(a), Some(1));
+        assert!(!arena.contains(a));
+
+        // The freed slot should be reusable, same as with the default `usize` index type.
+        let c = arena.try_insert(3).unwrap();
+        assert_eq!(*arena.get(c).unwrap(), 3);
+    }
+
+    #[test]
+    fn arena_holds_non_clone_values() {
+        // Deliberately no `#[derive(Clone)]`: this pins `GenerationalArena` not requiring
+        // `T: Clone`, so the bound can't be silently reintroduced on an impl block.
+        #[derive(Debug, PartialEq)]
+        struct NotClone(u32);
+
+        let mut arena = GenerationalArena::<NotClone, 1>::new();
+        let i = arena.try_insert(NotClone(42)).ok().unwrap();
+        assert_eq!(*arena.get(i).unwrap(), NotClone(42));
+        assert_eq!(arena.remove(i), Some(NotClone(42)));
+    }
 }